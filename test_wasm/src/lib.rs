@@ -9,15 +9,82 @@
 //! - Trap handling and forensic capture
 //! - Stateful computation
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
+use core::cell::UnsafeCell;
+#[cfg(not(test))]
 use core::panic::PanicInfo;
 
+#[cfg(not(test))]
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
     loop {}
 }
 
+// ============================================================================
+// Sound Interior Mutability
+// ============================================================================
+
+/// Interior-mutability wrapper replacing `static mut` globals.
+///
+/// `static mut` is exactly the pattern that fails MIRI's aliasing model:
+/// taking `&mut STATIC` manufactures a unique reference whose provenance
+/// MIRI can't prove is actually unique, which is undefined behavior under
+/// concurrent or reentrant host calls even if none ever happen in
+/// practice. Wrapping the value in `UnsafeCell` and only ever
+/// materializing a pointer for the duration of a single access keeps
+/// every export in this crate sound under MIRI while staying a
+/// zero-cost plain `static` on the wasm32 guest target this crate is
+/// actually compiled for.
+#[repr(transparent)]
+struct StateCell<T>(UnsafeCell<T>);
+
+// SAFETY: the guest runtime this crate targets is single-threaded, and the
+// native tests below hold `TEST_LOCK` while touching these statics, so no
+// two accesses to a `StateCell` are ever concurrent.
+unsafe impl<T> Sync for StateCell<T> {}
+
+impl<T> StateCell<T> {
+    const fn new(value: T) -> Self {
+        StateCell(UnsafeCell::new(value))
+    }
+
+    /// Returns a raw pointer to the wrapped value.
+    ///
+    /// # Safety
+    /// The caller must not hold any other live reference (shared or
+    /// exclusive) into the cell while using the returned pointer.
+    unsafe fn get(&self) -> *mut T {
+        self.0.get()
+    }
+}
+
+/// Diverge the same way `core::arch::wasm32::unreachable()` would, but
+/// also compile on the native target this crate's MIRI test target
+/// builds for (the wasm32 intrinsics module doesn't exist there).
+#[cfg(target_arch = "wasm32")]
+fn trap() -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn trap() -> ! {
+    unreachable!("guest trap")
+}
+
+/// Grow linear memory by `delta` pages, returning the previous size in
+/// pages or `usize::MAX` on failure. Gated the same way as `trap()`:
+/// `core::arch::wasm32::memory_grow` only exists for the wasm32 target.
+#[cfg(target_arch = "wasm32")]
+fn mem_grow(delta: usize) -> usize {
+    core::arch::wasm32::memory_grow::<0>(delta)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn mem_grow(_delta: usize) -> usize {
+    usize::MAX
+}
+
 // ============================================================================
 // Basic Operations
 // ============================================================================
@@ -84,7 +151,7 @@ pub extern "C" fn nested_loops(n: i32) -> i32 {
 // ============================================================================
 
 /// Static buffer for memory tests
-static mut BUFFER: [u8; 1024] = [0u8; 1024];
+static BUFFER: StateCell<[u8; 1024]> = StateCell::new([0u8; 1024]);
 
 /// Write a pattern to the buffer
 #[no_mangle]
@@ -92,7 +159,7 @@ pub extern "C" fn write_pattern(pattern: u8, length: i32) -> i32 {
     let len = length.min(1024) as usize;
     unsafe {
         for i in 0..len {
-            BUFFER[i] = pattern;
+            (*BUFFER.get())[i] = pattern;
         }
     }
     len as i32
@@ -102,7 +169,7 @@ pub extern "C" fn write_pattern(pattern: u8, length: i32) -> i32 {
 #[no_mangle]
 pub extern "C" fn read_buffer(index: i32) -> i32 {
     if index >= 0 && index < 1024 {
-        unsafe { BUFFER[index as usize] as i32 }
+        unsafe { (*BUFFER.get())[index as usize] as i32 }
     } else {
         -1
     }
@@ -113,7 +180,7 @@ pub extern "C" fn read_buffer(index: i32) -> i32 {
 pub extern "C" fn scan_for_pattern(pattern: u8) -> i32 {
     unsafe {
         for i in 0..1024 {
-            if BUFFER[i] == pattern {
+            if (*BUFFER.get())[i] == pattern {
                 return i as i32;
             }
         }
@@ -126,12 +193,86 @@ pub extern "C" fn scan_for_pattern(pattern: u8) -> i32 {
 pub extern "C" fn fill_incrementing() -> i32 {
     unsafe {
         for i in 0..1024 {
-            BUFFER[i] = (i % 256) as u8;
+            (*BUFFER.get())[i] = (i % 256) as u8;
         }
     }
     1024
 }
 
+// ============================================================================
+// Multi-Page Memory Growth
+// ============================================================================
+
+/// Size in bytes of one WASM linear-memory page.
+const PAGE_SIZE: usize = 65536;
+
+/// Grow linear memory by `pages` 64 KiB pages and fill each newly grown
+/// page with a distinct byte pattern (page index modulo 256), so the
+/// framework can test page-growth and large-memory isolation rather than
+/// just the fixed 1024-byte BUFFER.
+///
+/// Returns the memory size in pages before growth, or -1 on failure.
+#[no_mangle]
+pub extern "C" fn grow_and_fill(pages: i32) -> i32 {
+    if pages <= 0 {
+        return -1;
+    }
+    let previous_pages = mem_grow(pages as usize);
+    if previous_pages == usize::MAX {
+        return -1;
+    }
+    unsafe {
+        for page in 0..pages as usize {
+            let base = (previous_pages + page) * PAGE_SIZE;
+            let pattern = (page % 256) as u8;
+            for offset in 0..PAGE_SIZE {
+                *((base + offset) as *mut u8) = pattern;
+            }
+        }
+    }
+    previous_pages as i32
+}
+
+/// Sum the bytes of a single 64 KiB page, identified by its absolute page
+/// index, wrapping on overflow. Lets a test read back across the page
+/// boundary of a page grown by a separate `grow_and_fill` call.
+#[no_mangle]
+pub extern "C" fn checksum_page(page: i32) -> i32 {
+    if page < 0 {
+        return -1;
+    }
+    let base = (page as usize) * PAGE_SIZE;
+    let mut sum: i32 = 0;
+    unsafe {
+        for offset in 0..PAGE_SIZE {
+            sum = sum.wrapping_add(*((base + offset) as *const u8) as i32);
+        }
+    }
+    sum
+}
+
+/// Grow memory, dirty the newly grown high pages with a pattern derived
+/// from their absolute page index, then trap. Forensic capture must span
+/// the dump across the original pages and the newly grown, potentially
+/// non-contiguous dirtied pages.
+#[no_mangle]
+pub extern "C" fn grow_then_trap(pages: i32) -> i32 {
+    let grow_pages = pages.max(0) as usize;
+    let previous_pages = mem_grow(grow_pages);
+    if previous_pages != usize::MAX {
+        unsafe {
+            for page in 0..grow_pages {
+                let base = (previous_pages + page) * PAGE_SIZE;
+                let pattern = ((previous_pages + page) % 256) as u8;
+                for offset in 0..PAGE_SIZE {
+                    *((base + offset) as *mut u8) = pattern;
+                }
+            }
+        }
+    }
+    trap()
+}
+
 // ============================================================================
 // Deliberate Crashes (Traps)
 // ============================================================================
@@ -148,13 +289,18 @@ pub extern "C" fn trap_out_of_bounds() -> i32 {
 /// Trigger unreachable instruction
 #[no_mangle]
 pub extern "C" fn trap_unreachable() -> i32 {
-    core::arch::wasm32::unreachable()
+    trap()
 }
 
 /// Division by zero (may or may not trap depending on WASM semantics)
 #[no_mangle]
 pub extern "C" fn trap_div_zero(a: i32) -> i32 {
-    a / 0
+    // The divisor is a literal zero at the source level, but routing it
+    // through `black_box` hides that from rustc's `unconditional_panic`
+    // lint (which would otherwise refuse to compile this for *any*
+    // target, not just wasm32) while still producing a genuine runtime
+    // division-by-zero trap.
+    a / core::hint::black_box(0)
 }
 
 // ============================================================================
@@ -162,63 +308,74 @@ pub extern "C" fn trap_div_zero(a: i32) -> i32 {
 // ============================================================================
 
 /// Global state counter
-static mut STATE: i32 = 0;
+static STATE: StateCell<i32> = StateCell::new(0);
 
 /// History of state changes
-static mut HISTORY: [i32; 100] = [0i32; 100];
+static HISTORY: StateCell<[i32; 100]> = StateCell::new([0i32; 100]);
 
 /// Current history index
-static mut HISTORY_INDEX: usize = 0;
+static HISTORY_INDEX: StateCell<usize> = StateCell::new(0);
 
 /// Increment state and record in history
 #[no_mangle]
 pub extern "C" fn stateful_increment() -> i32 {
     unsafe {
-        STATE = STATE.wrapping_add(1);
-        if HISTORY_INDEX < 100 {
-            HISTORY[HISTORY_INDEX] = STATE;
-            HISTORY_INDEX += 1;
+        *STATE.get() = (*STATE.get()).wrapping_add(1);
+        if *HISTORY_INDEX.get() < 100 {
+            (*HISTORY.get())[*HISTORY_INDEX.get()] = *STATE.get();
+            *HISTORY_INDEX.get() += 1;
         }
-        STATE
+        *STATE.get()
     }
 }
 
 /// Get current state value
 #[no_mangle]
 pub extern "C" fn get_state() -> i32 {
-    unsafe { STATE }
+    unsafe { *STATE.get() }
 }
 
 /// Reset state to zero
 #[no_mangle]
 pub extern "C" fn reset_state() -> i32 {
     unsafe {
-        STATE = 0;
-        HISTORY_INDEX = 0;
+        *STATE.get() = 0;
+        *HISTORY_INDEX.get() = 0;
         for i in 0..100 {
-            HISTORY[i] = 0;
+            (*HISTORY.get())[i] = 0;
         }
     }
     0
 }
 
-/// Increment n times then crash
+/// Increment n times then crash.
 ///
-/// This is useful for forensic testing - we can verify that the
-/// memory dump contains STATE = n after the crash
-#[no_mangle]
-pub extern "C" fn crash_after_n(n: i32) -> i32 {
+/// Not `extern "C"` itself so that on the native test target, the panic
+/// `trap()` raises can unwind out of it normally: a panic that unwinds
+/// across a plain `extern "C"` function frame aborts the process instead
+/// of being caught, which would make `#[should_panic]` tests relying on
+/// this helper SIGABRT rather than pass or fail.
+fn crash_after_n_impl(n: i32) -> i32 {
     unsafe {
         for _ in 0..n {
-            STATE = STATE.wrapping_add(1);
-            if HISTORY_INDEX < 100 {
-                HISTORY[HISTORY_INDEX] = STATE;
-                HISTORY_INDEX += 1;
+            *STATE.get() = (*STATE.get()).wrapping_add(1);
+            if *HISTORY_INDEX.get() < 100 {
+                (*HISTORY.get())[*HISTORY_INDEX.get()] = *STATE.get();
+                *HISTORY_INDEX.get() += 1;
             }
         }
-        // Now crash - forensics should show STATE = n
-        core::arch::wasm32::unreachable()
     }
+    // Now crash - forensics should show STATE = n
+    trap()
+}
+
+/// Increment n times then crash
+///
+/// This is useful for forensic testing - we can verify that the
+/// memory dump contains STATE = n after the crash
+#[no_mangle]
+pub extern "C" fn crash_after_n(n: i32) -> i32 {
+    crash_after_n_impl(n)
 }
 
 /// Increment until fuel exhaustion
@@ -228,13 +385,63 @@ pub extern "C" fn crash_after_n(n: i32) -> i32 {
 pub extern "C" fn increment_until_exhausted() -> i32 {
     unsafe {
         loop {
-            STATE = STATE.wrapping_add(1);
-            if HISTORY_INDEX < 100 {
-                HISTORY[HISTORY_INDEX] = STATE;
-                HISTORY_INDEX += 1;
+            *STATE.get() = (*STATE.get()).wrapping_add(1);
+            if *HISTORY_INDEX.get() < 100 {
+                (*HISTORY.get())[*HISTORY_INDEX.get()] = *STATE.get();
+                *HISTORY_INDEX.get() += 1;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Host Import Boundary (resumable continuations)
+// ============================================================================
+
+extern "C" {
+    fn host_yield(token: i32) -> i32;
+    fn host_log(val: i32);
+}
+
+/// Call back into the host mid-computation, mutating STATE between calls.
+///
+/// Each iteration increments STATE, yields control to the host with the
+/// current STATE as the token, and folds the host's response back into
+/// STATE. This exercises suspending and resuming a guest across a host
+/// trap or fuel-exhaustion boundary: forensic capture taken mid-yield
+/// should show HISTORY holding exactly one entry per iteration completed so
+/// far, and STATE holding those increments plus every earlier host response.
+#[no_mangle]
+pub extern "C" fn compute_with_yields(n: i32) -> i32 {
+    unsafe {
+        for _ in 0..n {
+            *STATE.get() = (*STATE.get()).wrapping_add(1);
+            if *HISTORY_INDEX.get() < 100 {
+                (*HISTORY.get())[*HISTORY_INDEX.get()] = *STATE.get();
+                *HISTORY_INDEX.get() += 1;
             }
+            let response = host_yield(*STATE.get());
+            *STATE.get() = (*STATE.get()).wrapping_add(response);
         }
+        *STATE.get()
+    }
+}
+
+/// Trap inside a host-call return path.
+///
+/// Records `arg` into STATE, calls the host, and traps immediately after
+/// the host call returns rather than around it. Forensic capture should
+/// show the pre-call STATE alongside the in-flight argument, confirming
+/// capture still works when the trap lands on the return edge of a host
+/// call rather than inside guest-only code.
+#[no_mangle]
+pub extern "C" fn trap_after_host_call(arg: i32) -> i32 {
+    unsafe {
+        *STATE.get() = arg;
+        host_log(arg);
+        let _response = host_yield(arg);
     }
+    trap()
 }
 
 // ============================================================================
@@ -284,3 +491,575 @@ pub extern "C" fn count_primes(n: i32) -> i32 {
     }
     count
 }
+
+// ============================================================================
+// BLAKE3 Forensic Fingerprinting
+//
+// A minimal, single-threaded, constant-memory BLAKE3 implementation used
+// to fingerprint the regions the framework captures on trap. Keeping it
+// inline (rather than pulling in the `blake3` crate) means it runs the
+// same under fuel metering as the rest of this module and needs no heap.
+// ============================================================================
+
+const B3_IV: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+const B3_MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const B3_CHUNK_START: u32 = 1 << 0;
+const B3_CHUNK_END: u32 = 1 << 1;
+const B3_PARENT: u32 = 1 << 2;
+const B3_ROOT: u32 = 1 << 3;
+
+const B3_BLOCK_LEN: usize = 64;
+const B3_CHUNK_LEN: usize = 1024;
+
+/// Maximum number of chunks this inline implementation can merge. The test
+/// modules in this crate never hash more than a few KiB, so a small stack
+/// is plenty; growing it costs nothing but static memory.
+const B3_MAX_STACK: usize = 16;
+
+fn b3_g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn b3_round(state: &mut [u32; 16], m: &[u32; 16]) {
+    // Mix the columns.
+    b3_g(state, 0, 4, 8, 12, m[0], m[1]);
+    b3_g(state, 1, 5, 9, 13, m[2], m[3]);
+    b3_g(state, 2, 6, 10, 14, m[4], m[5]);
+    b3_g(state, 3, 7, 11, 15, m[6], m[7]);
+    // Mix the diagonals.
+    b3_g(state, 0, 5, 10, 15, m[8], m[9]);
+    b3_g(state, 1, 6, 11, 12, m[10], m[11]);
+    b3_g(state, 2, 7, 8, 13, m[12], m[13]);
+    b3_g(state, 3, 4, 9, 14, m[14], m[15]);
+}
+
+fn b3_permute(m: &[u32; 16]) -> [u32; 16] {
+    let mut permuted = [0u32; 16];
+    for i in 0..16 {
+        permuted[i] = m[B3_MSG_PERMUTATION[i]];
+    }
+    permuted
+}
+
+/// Compress one 64-byte block through 7 rounds of the G mixing function,
+/// feeding back the chaining value per the BLAKE3 compression function.
+fn b3_compress(
+    chaining_value: &[u32; 8],
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        chaining_value[0],
+        chaining_value[1],
+        chaining_value[2],
+        chaining_value[3],
+        chaining_value[4],
+        chaining_value[5],
+        chaining_value[6],
+        chaining_value[7],
+        B3_IV[0],
+        B3_IV[1],
+        B3_IV[2],
+        B3_IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block_words;
+    for round in 0..7 {
+        b3_round(&mut state, &m);
+        if round < 6 {
+            m = b3_permute(&m);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= chaining_value[i];
+    }
+    state
+}
+
+fn b3_words_from_block(block: &[u8]) -> [u32; 16] {
+    let mut padded = [0u8; B3_BLOCK_LEN];
+    padded[..block.len()].copy_from_slice(block);
+    let mut words = [0u32; 16];
+    for i in 0..16 {
+        words[i] = u32::from_le_bytes([
+            padded[i * 4],
+            padded[i * 4 + 1],
+            padded[i * 4 + 2],
+            padded[i * 4 + 3],
+        ]);
+    }
+    words
+}
+
+fn b3_words_to_bytes(words: [u32; 8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for i in 0..8 {
+        out[i * 4..i * 4 + 4].copy_from_slice(&words[i].to_le_bytes());
+    }
+    out
+}
+
+/// Chaining value for one <= 1024-byte chunk, split into 64-byte blocks.
+/// `root` applies the ROOT flag to the final block, which is only valid
+/// when this chunk is the entire (single-chunk) input.
+fn b3_chunk_chaining_value(chunk: &[u8], counter: u64, root: bool) -> [u32; 8] {
+    let mut cv = B3_IV;
+    let total_blocks = if chunk.is_empty() {
+        1
+    } else {
+        chunk.len().div_ceil(B3_BLOCK_LEN)
+    };
+    let mut offset = 0;
+    for block_idx in 0..total_blocks {
+        let end = (offset + B3_BLOCK_LEN).min(chunk.len());
+        let block = &chunk[offset..end];
+        let mut flags = 0u32;
+        if block_idx == 0 {
+            flags |= B3_CHUNK_START;
+        }
+        if block_idx == total_blocks - 1 {
+            flags |= B3_CHUNK_END;
+            if root {
+                flags |= B3_ROOT;
+            }
+        }
+        let words = b3_words_from_block(block);
+        let out = b3_compress(&cv, &words, counter, block.len() as u32, flags);
+        cv = [
+            out[0], out[1], out[2], out[3], out[4], out[5], out[6], out[7],
+        ];
+        offset += B3_BLOCK_LEN;
+    }
+    cv
+}
+
+/// Combine two child chaining values into their parent node.
+fn b3_parent_chaining_value(left: &[u32; 8], right: &[u32; 8], root: bool) -> [u32; 8] {
+    let mut block_words = [0u32; 16];
+    block_words[..8].copy_from_slice(left);
+    block_words[8..].copy_from_slice(right);
+    let flags = if root {
+        B3_PARENT | B3_ROOT
+    } else {
+        B3_PARENT
+    };
+    let out = b3_compress(&B3_IV, &block_words, 0, B3_BLOCK_LEN as u32, flags);
+    [
+        out[0], out[1], out[2], out[3], out[4], out[5], out[6], out[7],
+    ]
+}
+
+/// Hash `data` to a 32-byte BLAKE3 digest, chaining 1024-byte chunks into a
+/// binary Merkle tree and finalizing the root with the ROOT flag.
+fn b3_hash(data: &[u8]) -> [u8; 32] {
+    if data.len() <= B3_CHUNK_LEN {
+        return b3_words_to_bytes(b3_chunk_chaining_value(data, 0, true));
+    }
+
+    let num_chunks = data.len().div_ceil(B3_CHUNK_LEN);
+    let mut stack: [[u32; 8]; B3_MAX_STACK] = [[0u32; 8]; B3_MAX_STACK];
+    let mut stack_len: usize = 0;
+    let mut chunk_counter: u64 = 0;
+    let mut offset = 0;
+
+    // Process every chunk except the last with the standard complete-
+    // subtree auto-merge. None of these merges may be the root: whether
+    // the tree bottoms out here depends on the final chunk, handled below.
+    while (chunk_counter as usize) + 1 < num_chunks {
+        let end = offset + B3_CHUNK_LEN;
+        let mut cv = b3_chunk_chaining_value(&data[offset..end], chunk_counter, false);
+        let mut total_chunks = chunk_counter + 1;
+        while stack_len > 0 && total_chunks & 1 == 0 {
+            stack_len -= 1;
+            cv = b3_parent_chaining_value(&stack[stack_len], &cv, false);
+            total_chunks >>= 1;
+        }
+        stack[stack_len] = cv;
+        stack_len += 1;
+        chunk_counter += 1;
+        offset += B3_CHUNK_LEN;
+    }
+
+    // The last chunk's chaining value is merged down through whatever is
+    // left on the stack; only the merge that empties the stack is root.
+    let mut cv = b3_chunk_chaining_value(&data[offset..], chunk_counter, false);
+    while stack_len > 0 {
+        stack_len -= 1;
+        cv = b3_parent_chaining_value(&stack[stack_len], &cv, stack_len == 0);
+    }
+    b3_words_to_bytes(cv)
+}
+
+/// Scratch output for the hash exports below, read back via
+/// `read_hash_byte` the same way `BUFFER` is read via `read_buffer`.
+static HASH_OUTPUT: StateCell<[u8; 32]> = StateCell::new([0u8; 32]);
+
+/// Read one byte of the last computed digest.
+#[no_mangle]
+pub extern "C" fn read_hash_byte(index: i32) -> i32 {
+    if index >= 0 && index < 32 {
+        unsafe { (*HASH_OUTPUT.get())[index as usize] as i32 }
+    } else {
+        -1
+    }
+}
+
+/// Hash the 1024-byte memory buffer and store the digest for readback.
+#[no_mangle]
+pub extern "C" fn hash_buffer() -> i32 {
+    let digest = unsafe { b3_hash(&*BUFFER.get()) };
+    unsafe {
+        *HASH_OUTPUT.get() = digest;
+    }
+    32
+}
+
+fn b3_snapshot_state_and_history() -> [u8; 4 + 100 * 4] {
+    let mut bytes = [0u8; 4 + 100 * 4];
+    unsafe {
+        bytes[0..4].copy_from_slice(&(*STATE.get()).to_le_bytes());
+        for i in 0..100 {
+            bytes[4 + i * 4..8 + i * 4].copy_from_slice(&(*HISTORY.get())[i].to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Hash STATE followed by the full HISTORY array and store the digest for
+/// readback, so forensic tests can assert against a known 32-byte digest
+/// instead of comparing STATE and HISTORY field by field.
+#[no_mangle]
+pub extern "C" fn hash_state_and_history() -> i32 {
+    let bytes = b3_snapshot_state_and_history();
+    let digest = b3_hash(&bytes);
+    unsafe {
+        *HASH_OUTPUT.get() = digest;
+    }
+    32
+}
+
+/// Increment STATE n times, hash STATE+HISTORY right before crashing, and
+/// store the digest so a post-crash memory dump can be checked against it.
+/// Split from the `extern "C"` export the same way `crash_after_n` is, so
+/// the trap at the end can be exercised directly from a `#[should_panic]`
+/// test without panicking across the FFI boundary.
+fn hash_after_n_impl(n: i32) -> i32 {
+    unsafe {
+        for _ in 0..n {
+            *STATE.get() = (*STATE.get()).wrapping_add(1);
+            if *HISTORY_INDEX.get() < 100 {
+                (*HISTORY.get())[*HISTORY_INDEX.get()] = *STATE.get();
+                *HISTORY_INDEX.get() += 1;
+            }
+        }
+    }
+    let bytes = b3_snapshot_state_and_history();
+    let digest = b3_hash(&bytes);
+    unsafe {
+        *HASH_OUTPUT.get() = digest;
+    }
+    trap()
+}
+
+#[no_mangle]
+pub extern "C" fn hash_after_n(n: i32) -> i32 {
+    hash_after_n_impl(n)
+}
+
+// ============================================================================
+// Minidump-Style Structured Region Emission
+//
+// Lays out a header, a region-descriptor table, and the data regions
+// inside BUFFER, mirroring a minidump's memory-list stream, so the
+// framework's dump reader can parse declared regions out of captured
+// memory rather than scanning a flat buffer for a pattern.
+// ============================================================================
+
+/// Magic value identifying a seeded region table ("MUNI" little-endian).
+const REGION_MAGIC: u32 = u32::from_le_bytes(*b"MUNI");
+
+/// Number of regions described by the table.
+const REGION_COUNT: usize = 4;
+
+/// Size in bytes of one {offset, len, tag} region descriptor.
+const REGION_DESC_LEN: usize = 12;
+
+/// Size in bytes of the header (magic + region count).
+const REGION_HEADER_LEN: usize = 8;
+
+/// Byte offset of the descriptor table, right after the header.
+const REGION_TABLE_OFFSET: usize = REGION_HEADER_LEN;
+
+/// Byte offset of the first region's data, right after the table.
+const REGION_DATA_START: usize = REGION_HEADER_LEN + REGION_COUNT * REGION_DESC_LEN;
+
+/// Size in bytes of each region's data, splitting the rest of BUFFER evenly.
+const REGION_DATA_LEN: usize = (1024 - REGION_DATA_START) / REGION_COUNT;
+
+/// Fill pattern byte for region `index`, distinct per region so a dump
+/// reader can tell regions apart.
+fn region_pattern(index: usize) -> u8 {
+    0xA0u8.wrapping_add(index as u8)
+}
+
+/// Write the header and descriptor table into BUFFER, then fill only the
+/// first `populated` regions' data with their pattern (later regions are
+/// left as-is), so callers can seed a full or partial dump.
+fn write_region_table(populated: usize) {
+    unsafe {
+        let buffer = &mut *BUFFER.get();
+        buffer[0..4].copy_from_slice(&REGION_MAGIC.to_le_bytes());
+        buffer[4..8].copy_from_slice(&(REGION_COUNT as u32).to_le_bytes());
+        for index in 0..REGION_COUNT {
+            let desc_offset = REGION_TABLE_OFFSET + index * REGION_DESC_LEN;
+            let data_offset = REGION_DATA_START + index * REGION_DATA_LEN;
+            buffer[desc_offset..desc_offset + 4]
+                .copy_from_slice(&(data_offset as u32).to_le_bytes());
+            buffer[desc_offset + 4..desc_offset + 8]
+                .copy_from_slice(&(REGION_DATA_LEN as u32).to_le_bytes());
+            buffer[desc_offset + 8..desc_offset + 12]
+                .copy_from_slice(&(index as u32).to_le_bytes());
+            if index < populated {
+                let pattern = region_pattern(index);
+                for offset in 0..REGION_DATA_LEN {
+                    buffer[data_offset + offset] = pattern;
+                }
+            }
+        }
+    }
+}
+
+/// Write the header, descriptor table, and pattern-filled data for every
+/// region into BUFFER.
+#[no_mangle]
+pub extern "C" fn seed_regions() -> i32 {
+    write_region_table(REGION_COUNT);
+    REGION_COUNT as i32
+}
+
+/// Not `extern "C"` itself, so that on the native test target a panic
+/// from `trap()` unwinds normally instead of aborting the process at an
+/// `extern "C"` frame boundary (see `crash_after_n_impl`).
+fn crash_with_regions_impl(n: i32) -> i32 {
+    write_region_table((n.max(0) as usize).min(REGION_COUNT));
+    trap()
+}
+
+/// Seed the header and descriptor table, fill only the first `n` regions
+/// with their pattern data (later regions' data is left as it was), then crash -
+/// exercises capture of a dump where some declared regions never got
+/// written before the trap.
+#[no_mangle]
+pub extern "C" fn crash_with_regions(n: i32) -> i32 {
+    crash_with_regions_impl(n)
+}
+
+/// Parse the descriptor table out of BUFFER and confirm every declared
+/// region's data matches its expected pattern.
+///
+/// Returns 0 if every region matches, -1 if the header magic is missing,
+/// or `index + 1` for the first region whose data doesn't match.
+#[no_mangle]
+pub extern "C" fn verify_regions() -> i32 {
+    unsafe {
+        let buffer = &*BUFFER.get();
+        let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+        if magic != REGION_MAGIC {
+            return -1;
+        }
+        for index in 0..REGION_COUNT {
+            let desc_offset = REGION_TABLE_OFFSET + index * REGION_DESC_LEN;
+            let data_offset = u32::from_le_bytes([
+                buffer[desc_offset],
+                buffer[desc_offset + 1],
+                buffer[desc_offset + 2],
+                buffer[desc_offset + 3],
+            ]) as usize;
+            let data_len = u32::from_le_bytes([
+                buffer[desc_offset + 4],
+                buffer[desc_offset + 5],
+                buffer[desc_offset + 6],
+                buffer[desc_offset + 7],
+            ]) as usize;
+            let expected = region_pattern(index);
+            for offset in 0..data_len {
+                if buffer[data_offset + offset] != expected {
+                    return (index + 1) as i32;
+                }
+            }
+        }
+    }
+    0
+}
+
+// ============================================================================
+// MIRI Soundness Tests
+//
+// Native test target (not wasm32) exercising the StateCell accessors for
+// aliasing UB. Run with `cargo +nightly miri test` from a host that has a
+// nightly toolchain with the miri component installed.
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes the tests that touch the shared statics.
+    static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn lock() -> std::sync::MutexGuard<'static, ()> {
+        TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn stateful_increment_is_sound_under_repeated_access() {
+        let _guard = lock();
+        reset_state();
+        for expected in 1..=10 {
+            assert_eq!(stateful_increment(), expected);
+        }
+        assert_eq!(get_state(), 10);
+    }
+
+    #[test]
+    fn write_pattern_is_sound_under_repeated_access() {
+        let _guard = lock();
+        write_pattern(0xAB, 16);
+        for i in 0..16 {
+            assert_eq!(read_buffer(i), 0xAB);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_after_n_traps_after_recording_state() {
+        let _guard = lock();
+        reset_state();
+        // Goes through the safe inner fn, not the `extern "C"` export: a
+        // panic unwinding across an `extern "C"` frame aborts the process
+        // instead of being caught by `#[should_panic]`.
+        crash_after_n_impl(3);
+    }
+
+    #[test]
+    fn b3_hash_matches_reference_digest_across_two_exact_chunks() {
+        // 2048 bytes is exactly two 1024-byte chunks: the first chunk is
+        // stacked without a merge, and the ROOT flag must land only on the
+        // parent node formed when the final chunk is merged in.
+        let mut data = [0u8; 2048];
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = (i % 256) as u8;
+        }
+        let expected: [u8; 32] = [
+            0x1b, 0xdc, 0xcf, 0xde, 0x02, 0x10, 0xa8, 0xca, 0x17, 0x8b, 0xe1, 0x9c, 0x67, 0x77,
+            0xcd, 0xb4, 0xb9, 0xa8, 0xfd, 0x24, 0xe7, 0xfe, 0x2b, 0x6b, 0x25, 0x9b, 0x98, 0xe7,
+            0xaa, 0xaa, 0x0b, 0xb6,
+        ];
+        assert_eq!(b3_hash(&data), expected);
+    }
+
+    #[test]
+    fn b3_hash_matches_reference_digest_across_four_exact_chunks() {
+        // 4096 bytes is four chunks, exercising a deeper power-of-two merge.
+        let mut data = [0u8; 4096];
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = (i % 256) as u8;
+        }
+        let expected: [u8; 32] = [
+            0x0b, 0x3d, 0xda, 0x6f, 0xbf, 0xe0, 0x1c, 0x93, 0xd7, 0x93, 0x88, 0x63, 0x2f, 0x66,
+            0xc5, 0xc1, 0xfa, 0x78, 0x13, 0x82, 0x8c, 0xa8, 0xf6, 0x2e, 0xf8, 0x63, 0x04, 0xee,
+            0x31, 0x03, 0x68, 0x97,
+        ];
+        assert_eq!(b3_hash(&data), expected);
+    }
+
+    #[test]
+    fn b3_hash_matches_reference_digest_for_a_partial_final_chunk() {
+        // 3000 bytes is not an exact multiple of 1024, so the final chunk
+        // is short (952 bytes).
+        let mut data = [0u8; 3000];
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = (i % 256) as u8;
+        }
+        let expected: [u8; 32] = [
+            0x6c, 0x94, 0x39, 0x46, 0xa7, 0x07, 0x94, 0xf2, 0xe1, 0x4c, 0x78, 0x5d, 0x5e, 0xe8,
+            0x8d, 0x30, 0x0d, 0x5f, 0x9b, 0x91, 0xd1, 0xb4, 0xef, 0x88, 0x30, 0x29, 0x74, 0xac,
+            0x4b, 0x06, 0x90, 0x52,
+        ];
+        assert_eq!(b3_hash(&data), expected);
+    }
+
+    #[test]
+    fn hash_buffer_round_trips_through_read_hash_byte() {
+        let _guard = lock();
+        write_pattern(0x7A, 1024);
+        assert_eq!(hash_buffer(), 32);
+        let expected = b3_hash(&[0x7Au8; 1024]);
+        for (i, expected_byte) in expected.iter().enumerate() {
+            assert_eq!(read_hash_byte(i as i32), *expected_byte as i32);
+        }
+    }
+
+    #[test]
+    fn hash_state_and_history_round_trips_through_read_hash_byte() {
+        let _guard = lock();
+        reset_state();
+        stateful_increment();
+        stateful_increment();
+        assert_eq!(hash_state_and_history(), 32);
+        let expected = b3_hash(&b3_snapshot_state_and_history());
+        for (i, expected_byte) in expected.iter().enumerate() {
+            assert_eq!(read_hash_byte(i as i32), *expected_byte as i32);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn hash_after_n_traps_after_storing_the_digest() {
+        let _guard = lock();
+        reset_state();
+        // Same reasoning as crash_after_n_impl: call the safe inner fn so
+        // the panic doesn't try to unwind across the extern "C" export.
+        hash_after_n_impl(3);
+    }
+
+    #[test]
+    fn seed_regions_round_trips_through_verify_regions() {
+        let _guard = lock();
+        assert_eq!(seed_regions(), REGION_COUNT as i32);
+        assert_eq!(verify_regions(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_with_regions_traps_after_seeding_the_table() {
+        let _guard = lock();
+        // Same reasoning as crash_after_n_impl: call the safe inner fn so
+        // the panic doesn't try to unwind across the extern "C" export.
+        crash_with_regions_impl(2);
+    }
+}